@@ -0,0 +1,92 @@
+use ark_ff::{
+    BigInteger, Field, Fp256, Fp256Parameters, Fp320, Fp320Parameters, Fp384, Fp384Parameters,
+    Fp768, Fp768Parameters, Fp832, Fp832Parameters, PrimeField, ToConstraintField,
+};
+use ark_std::vec::Vec;
+
+/// An interface for objects that can be absorbed by a `CryptographicSponge`.
+pub trait Absorb {
+    /// Converts the object into a list of bytes that can be absorbed by a `CryptographicSponge`.
+    fn to_sponge_bytes(&self, dest: &mut Vec<u8>);
+
+    /// Converts the object into field elements that can be absorbed by a `CryptographicSponge`.
+    fn to_sponge_field_elements<F: PrimeField>(&self, dest: &mut Vec<F>);
+
+    /// Convenience method that allocates and returns the field elements directly.
+    fn to_sponge_field_elements_as_vec<F: PrimeField>(&self) -> Vec<F> {
+        let mut dest = Vec::new();
+        self.to_sponge_field_elements(&mut dest);
+        dest
+    }
+}
+
+impl Absorb for u8 {
+    fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+        dest.push(*self);
+    }
+
+    fn to_sponge_field_elements<F: PrimeField>(&self, dest: &mut Vec<F>) {
+        dest.push(F::from(*self));
+    }
+}
+
+/// Implements `Absorb` for a prime-field type. Absorbing into the same field (the common case of
+/// a sponge absorbing its own native field elements) maps the value straight across as a single
+/// element; absorbing into a different field packs the canonical little-endian byte encoding
+/// instead, since the two fields' moduli need not agree.
+///
+/// This has to enumerate the concrete `Fp*` types rather than a single `impl<F: PrimeField>
+/// Absorb for F`, since a blanket impl over a foreign trait's bound would conflict with the `u8`
+/// impl above under coherence rules.
+macro_rules! impl_absorbable_field {
+    ($field:ident, $params:ident) => {
+        impl<P: $params> Absorb for $field<P> {
+            fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+                dest.extend_from_slice(&self.into_repr().to_bytes_le());
+            }
+
+            fn to_sponge_field_elements<CF: PrimeField>(&self, dest: &mut Vec<CF>) {
+                if CF::characteristic() == Self::characteristic() {
+                    dest.push(CF::from_le_bytes_mod_order(&self.into_repr().to_bytes_le()));
+                } else {
+                    let elems: Vec<CF> = self
+                        .into_repr()
+                        .to_bytes_le()
+                        .to_field_elements()
+                        .expect("byte-to-field-element packing is infallible");
+                    dest.extend(elems);
+                }
+            }
+        }
+    };
+}
+
+impl_absorbable_field!(Fp256, Fp256Parameters);
+impl_absorbable_field!(Fp320, Fp320Parameters);
+impl_absorbable_field!(Fp384, Fp384Parameters);
+impl_absorbable_field!(Fp768, Fp768Parameters);
+impl_absorbable_field!(Fp832, Fp832Parameters);
+
+impl<A: Absorb> Absorb for [A] {
+    fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+        for elem in self {
+            elem.to_sponge_bytes(dest);
+        }
+    }
+
+    fn to_sponge_field_elements<F: PrimeField>(&self, dest: &mut Vec<F>) {
+        for elem in self {
+            elem.to_sponge_field_elements(dest);
+        }
+    }
+}
+
+impl<A: Absorb> Absorb for Vec<A> {
+    fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+        self.as_slice().to_sponge_bytes(dest)
+    }
+
+    fn to_sponge_field_elements<F: PrimeField>(&self, dest: &mut Vec<F>) {
+        self.as_slice().to_sponge_field_elements(dest)
+    }
+}
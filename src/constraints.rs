@@ -0,0 +1,236 @@
+use ark_ec::{SWModelParameters, TEModelParameters};
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::groups::curves::short_weierstrass::{AffineVar, ProjectiveVar};
+use ark_r1cs_std::groups::curves::twisted_edwards::AffineVar as TEAffineVar;
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_std::vec::Vec;
+
+use crate::CryptographicSponge;
+
+/// The interface for a cryptographic sponge constraints on field `F`.
+/// A sponge can `absorb` or `squeeze` in-circuit variables.
+pub trait CryptographicSpongeVar<F: PrimeField, S: CryptographicSponge> {
+    /// Parameters used by the sponge.
+    type Parameters;
+
+    /// Initialize a new instance of the sponge.
+    fn new(cs: ConstraintSystemRef<F>, params: &Self::Parameters) -> Self;
+
+    /// Returns the constraint system used by the sponge.
+    fn cs(&self) -> ConstraintSystemRef<F>;
+
+    /// Absorb an input into the sponge.
+    fn absorb(&mut self, input: &impl AbsorbGadget<F>) -> Result<(), SynthesisError>;
+
+    /// Squeeze `num_bytes` bytes from the sponge.
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Result<Vec<UInt8<F>>, SynthesisError>;
+
+    /// Squeeze `num_bits` bits from the sponge.
+    fn squeeze_bits(&mut self, num_bits: usize) -> Result<Vec<Boolean<F>>, SynthesisError>;
+
+    /// Squeeze `num_elements` field elements from the sponge.
+    fn squeeze_field_elements(
+        &mut self,
+        num_elements: usize,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError>;
+}
+
+/// An interface for objects that can be absorbed by a `CryptographicSpongeVar` whose constraint
+/// field is `F`.
+pub trait AbsorbGadget<F: PrimeField> {
+    /// Converts the object into a list of bytes that can be absorbed by a `CryptographicSpongeVar`.
+    fn to_sponge_bytes(&self) -> Result<Vec<UInt8<F>>, SynthesisError>;
+
+    /// Converts the object into field elements that can be absorbed by a `CryptographicSpongeVar`.
+    fn to_sponge_field_elements(&self) -> Result<Vec<FpVar<F>>, SynthesisError>;
+}
+
+impl<F: PrimeField> AbsorbGadget<F> for FpVar<F> {
+    fn to_sponge_bytes(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        self.to_bytes()
+    }
+
+    fn to_sponge_field_elements(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        Ok(vec![self.clone()])
+    }
+}
+
+impl<F: PrimeField> AbsorbGadget<F> for UInt8<F> {
+    fn to_sponge_bytes(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        Ok(vec![self.clone()])
+    }
+
+    fn to_sponge_field_elements(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        Ok(vec![Boolean::le_bits_to_fp_var(&self.to_bits_le()?)?])
+    }
+}
+
+impl<F: PrimeField, A: AbsorbGadget<F>> AbsorbGadget<F> for &[A] {
+    fn to_sponge_bytes(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        let mut bytes = Vec::new();
+        for elem in self.iter() {
+            bytes.extend(elem.to_sponge_bytes()?);
+        }
+        Ok(bytes)
+    }
+
+    fn to_sponge_field_elements(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let mut elems = Vec::new();
+        for elem in self.iter() {
+            elems.extend(elem.to_sponge_field_elements()?);
+        }
+        Ok(elems)
+    }
+}
+
+impl<F: PrimeField, A: AbsorbGadget<F>> AbsorbGadget<F> for Vec<A> {
+    fn to_sponge_bytes(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        self.as_slice().to_sponge_bytes()
+    }
+
+    fn to_sponge_field_elements(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        self.as_slice().to_sponge_field_elements()
+    }
+}
+
+/// Absorbs a short-Weierstrass affine point as its `(x, y)` coordinates followed by an infinity
+/// flag (`1` for the point at infinity, `0` otherwise). This crate has no matching native
+/// `Absorb` impl for curve points.
+impl<P: SWModelParameters<BaseField = F>, F: PrimeField> AbsorbGadget<F>
+    for AffineVar<P, FpVar<F>>
+{
+    fn to_sponge_bytes(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        self.to_sponge_field_elements()?.to_sponge_bytes()
+    }
+
+    fn to_sponge_field_elements(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let infinity = self
+            .infinity
+            .select(&FpVar::constant(F::one()), &FpVar::zero())?;
+        Ok(vec![self.x.clone(), self.y.clone(), infinity])
+    }
+}
+
+/// Absorbs a short-Weierstrass projective point by first normalizing it to affine coordinates
+/// (dividing through by `z`), then absorbing `(x, y)` and the infinity flag in the same order as
+/// `AffineVar`. The point at infinity absorbs as `(0, 0, 1)`.
+///
+/// Unlike `AffineVar`, `ProjectiveVar` has no `infinity` field: the identity is the reachable
+/// witness value `z == 0` (e.g. the result of a scalar multiplication by zero, or of adding a
+/// point to its negation). Inverting `z` unconditionally would make that witness unsatisfiable,
+/// so the infinity flag is derived from `z.is_eq(&0)`, and the inverse is taken of a
+/// conditionally-substituted nonzero value instead of `z` itself.
+impl<P: SWModelParameters<BaseField = F>, F: PrimeField> AbsorbGadget<F>
+    for ProjectiveVar<P, FpVar<F>>
+{
+    fn to_sponge_bytes(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        self.to_sponge_field_elements()?.to_sponge_bytes()
+    }
+
+    fn to_sponge_field_elements(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let infinity = self.z.is_eq(&FpVar::zero())?;
+        let safe_z = infinity.select(&FpVar::one(), &self.z)?;
+        let z_inv = safe_z.inverse()?;
+        let x = infinity.select(&FpVar::zero(), &(&self.x * &z_inv))?;
+        let y = infinity.select(&FpVar::zero(), &(&self.y * &z_inv))?;
+        let infinity = infinity.select(&FpVar::constant(F::one()), &FpVar::zero())?;
+        Ok(vec![x, y, infinity])
+    }
+}
+
+/// Absorbs a twisted-Edwards affine point as its `(x, y)` coordinates. Unlike the
+/// short-Weierstrass model, the identity is a regular affine point here, so no separate infinity
+/// flag is needed.
+impl<P: TEModelParameters<BaseField = F>, F: PrimeField> AbsorbGadget<F>
+    for TEAffineVar<P, FpVar<F>>
+{
+    fn to_sponge_bytes(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        self.to_sponge_field_elements()?.to_sponge_bytes()
+    }
+
+    fn to_sponge_field_elements(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        Ok(vec![self.x.clone(), self.y.clone()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::ProjectiveCurve;
+    use ark_ed_on_bls12_381::EdwardsProjective;
+    use ark_ff::{One, Zero};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, UniformRand};
+    use ark_test_curves::bls12_381::{Fq, G1Projective, Parameters as G1Parameters};
+
+    type G1ProjectiveVar = ProjectiveVar<G1Parameters, FpVar<Fq>>;
+
+    fn values<F: PrimeField>(elems: &[FpVar<F>]) -> Vec<F> {
+        elems.iter().map(|e| e.value().unwrap()).collect()
+    }
+
+    #[test]
+    fn affine_absorb_gadget_is_satisfied() {
+        let mut rng = test_rng();
+        let cs = ConstraintSystem::<Fq>::new_ref();
+
+        let point = G1Projective::rand(&mut rng);
+        let point_var = G1ProjectiveVar::new_witness(cs.clone(), || Ok(point))
+            .unwrap()
+            .to_affine()
+            .unwrap();
+
+        let elems = AbsorbGadget::to_sponge_field_elements(&point_var).unwrap();
+        let affine = point.into_affine();
+        assert_eq!(values(&elems), vec![affine.x, affine.y, Fq::zero()]);
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn projective_absorb_gadget_matches_its_affine_form() {
+        let mut rng = test_rng();
+        let cs = ConstraintSystem::<Fq>::new_ref();
+
+        let point = G1Projective::rand(&mut rng);
+        let projective_var = G1ProjectiveVar::new_witness(cs.clone(), || Ok(point)).unwrap();
+        let affine_var = projective_var.to_affine().unwrap();
+
+        let projective_elems = AbsorbGadget::to_sponge_field_elements(&projective_var).unwrap();
+        let affine_elems = AbsorbGadget::to_sponge_field_elements(&affine_var).unwrap();
+
+        assert_eq!(values(&projective_elems), values(&affine_elems));
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn projective_absorb_gadget_handles_the_point_at_infinity() {
+        // The identity is the reachable witness value z == 0 (e.g. the result of a zero scalar
+        // multiplication); to_sponge_field_elements must not divide by z directly, or this
+        // allocation becomes unsatisfiable.
+        let cs = ConstraintSystem::<Fq>::new_ref();
+
+        let point_var =
+            G1ProjectiveVar::new_witness(cs.clone(), || Ok(G1Projective::zero())).unwrap();
+        let elems = AbsorbGadget::to_sponge_field_elements(&point_var).unwrap();
+
+        assert_eq!(values(&elems), vec![Fq::zero(), Fq::zero(), Fq::one()]);
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn te_affine_absorb_gadget_is_satisfied() {
+        use ark_ed_on_bls12_381::{constraints::EdwardsVar, Fq as EdFq};
+
+        let mut rng = test_rng();
+        let cs = ConstraintSystem::<EdFq>::new_ref();
+
+        let point = EdwardsProjective::rand(&mut rng).into_affine();
+        let point_var = EdwardsVar::new_witness(cs.clone(), || Ok(point)).unwrap();
+
+        let elems = AbsorbGadget::to_sponge_field_elements(&point_var).unwrap();
+        assert_eq!(values(&elems), vec![point.x, point.y]);
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
@@ -0,0 +1,47 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+//! A crate for the cryptographic sponge trait.
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate ark_std;
+
+use ark_ff::PrimeField;
+use ark_std::vec::Vec;
+
+mod absorb;
+pub use absorb::*;
+
+/// R1CS gadgets for the traits in this crate.
+pub mod constraints;
+/// An instantiation of `CryptographicSponge` using the Poseidon permutation.
+pub mod poseidon;
+
+/// The interface for a cryptographic sponge. A sponge can `absorb` inputs and later `squeeze`
+/// bytes, bits or field elements.
+pub trait CryptographicSponge: Clone {
+    /// Parameters used by the sponge.
+    type Parameters;
+
+    /// Initialize a new instance of the sponge.
+    fn new(params: &Self::Parameters) -> Self;
+
+    /// Absorb an input into the sponge.
+    fn absorb(&mut self, input: &impl Absorb);
+
+    /// Squeeze `num_bytes` bytes from the sponge.
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Vec<u8>;
+
+    /// Squeeze `num_bits` bits from the sponge.
+    fn squeeze_bits(&mut self, num_bits: usize) -> Vec<bool>;
+
+    /// Squeeze `num_elements` nonnative field elements from the sponge.
+    fn squeeze_field_elements<F: PrimeField>(&mut self, num_elements: usize) -> Vec<F>;
+}
+
+/// The interface for a cryptographic sponge whose native field elements are `F`.
+pub trait FieldBasedCryptographicSponge<F: PrimeField>: CryptographicSponge {
+    /// Squeeze `num_elements` field elements from the sponge, over its native field.
+    fn squeeze_native_field_elements(&mut self, num_elements: usize) -> Vec<F>;
+}
@@ -1,6 +1,8 @@
 use crate::constraints::AbsorbGadget;
 use crate::constraints::CryptographicSpongeVar;
-use crate::poseidon::{PoseidonParameters, PoseidonSponge, PoseidonSpongeMode};
+use crate::poseidon::{
+    OptimizedMds, PoseidonParameters, PoseidonSponge, PoseidonSpongeMode, SparseMdsMatrix,
+};
 use ark_ff::{FpParameters, PrimeField};
 use ark_r1cs_std::fields::fp::FpVar;
 use ark_r1cs_std::prelude::*;
@@ -8,6 +10,59 @@ use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
 use ark_std::vec;
 use ark_std::vec::Vec;
 
+/// A single step of an [`PoseidonSpongeVar::new_with_io_pattern`] declaration: the next
+/// non-empty call must absorb or squeeze exactly this many field elements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IOPatternOp {
+    /// the next field elements absorbed
+    Absorb(usize),
+    /// the next field elements squeezed
+    Squeeze(usize),
+}
+
+impl IOPatternOp {
+    fn len(&self) -> usize {
+        match self {
+            IOPatternOp::Absorb(len) | IOPatternOp::Squeeze(len) => *len,
+        }
+    }
+
+    fn direction(&self) -> IOPatternDirection {
+        match self {
+            IOPatternOp::Absorb(_) => IOPatternDirection::Absorb,
+            IOPatternOp::Squeeze(_) => IOPatternDirection::Squeeze,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IOPatternDirection {
+    Absorb,
+    Squeeze,
+}
+
+/// Encode an IO pattern into a single field-element domain-separation tag, folding in each op's
+/// direction and length in order. Mixed into the sponge's initial capacity by
+/// [`PoseidonSpongeVar::new_with_io_pattern`].
+///
+/// Each op folds in as `2 * len + direction` (the low bit recovers the direction, the rest the
+/// length) rolled forward through a base of `2^96`, comfortably wider than that `u64`-length
+/// contribution can ever be, so — unlike a fixed-width bitfield split — no length can collide
+/// with the direction of a shorter op. The encoding is exact only while the rolled-forward value
+/// stays below the field modulus; past that it is reduced modulo it. A leading `Absorb(0)`
+/// contributes zero and leaves the tag unchanged.
+fn io_pattern_tag<F: PrimeField>(pattern: &[IOPatternOp]) -> F {
+    let op_base = F::from(1u128 << 96);
+    pattern.iter().fold(F::zero(), |tag, op| {
+        let direction = match op.direction() {
+            IOPatternDirection::Absorb => F::zero(),
+            IOPatternDirection::Squeeze => F::one(),
+        };
+        let contribution = F::from(op.len() as u64).double() + direction;
+        tag * op_base + contribution
+    })
+}
+
 #[derive(Clone)]
 /// the gadget for Poseidon sponge
 ///
@@ -35,12 +90,27 @@ pub struct PoseidonSpongeVar<F: PrimeField> {
     pub rate: usize,
     /// the capacity
     pub capacity: usize,
+    /// Precomputed sparse-matrix decomposition of `mds`, if the parameters were built with
+    /// [`PoseidonParameters::with_optimized_mds`]. When present, `permute` uses it in place of
+    /// the dense `mds` product on every partial round.
+    pub optimized_mds: Option<OptimizedMds<F>>,
 
     // Sponge State
     /// the sponge's state
     pub state: Vec<FpVar<F>>,
     /// the mode
     mode: PoseidonSpongeMode,
+
+    /// The IO pattern declared via [`Self::new_with_io_pattern`], if any. Each `absorb` /
+    /// `squeeze_field_elements` call is checked against the entry at `io_pattern_index`.
+    io_pattern: Option<Vec<IOPatternOp>>,
+    /// index into `io_pattern` of the entry currently being consumed
+    io_pattern_index: usize,
+    /// length of the current `io_pattern` entry, which the next non-empty call must match exactly
+    io_pattern_remaining: usize,
+    /// the domain-separation tag mixed into the initial capacity, if any; `reset` re-applies it
+    /// so a reset sponge is indistinguishable from a freshly constructed one.
+    io_pattern_tag: Option<F>,
 }
 
 impl<F: PrimeField> PoseidonSpongeVar<F> {
@@ -53,12 +123,12 @@ impl<F: PrimeField> PoseidonSpongeVar<F> {
         // Full rounds apply the S Box (x^alpha) to every element of state
         if is_full_round {
             for state_item in state.iter_mut() {
-                *state_item = state_item.pow_by_constant(&[self.alpha])?;
+                *state_item = state_item.pow_by_constant([self.alpha])?;
             }
         }
         // Partial rounds apply the S Box (x^alpha) to just the final element of state
         else {
-            state[state.len() - 1] = state[state.len() - 1].pow_by_constant(&[self.alpha])?;
+            state[state.len() - 1] = state[state.len() - 1].pow_by_constant([self.alpha])?;
         }
 
         Ok(())
@@ -74,38 +144,81 @@ impl<F: PrimeField> PoseidonSpongeVar<F> {
 
     #[tracing::instrument(target = "r1cs", skip(self))]
     fn apply_mds(&self, state: &mut [FpVar<F>]) -> Result<(), SynthesisError> {
-        let mut new_state = Vec::new();
+        self.apply_dense_mds(state, &self.mds)
+    }
+
+    #[tracing::instrument(target = "r1cs", skip(self, mds))]
+    fn apply_dense_mds(&self, state: &mut [FpVar<F>], mds: &[Vec<F>]) -> Result<(), SynthesisError> {
         let zero = FpVar::<F>::zero();
-        for i in 0..state.len() {
-            let mut cur = zero.clone();
-            for (j, state_elem) in state.iter().enumerate() {
-                let term = state_elem * self.mds[i][j];
-                cur += &term;
-            }
-            new_state.push(cur);
-        }
+        let new_state: Vec<FpVar<F>> = mds
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(state.iter())
+                    .fold(zero.clone(), |cur, (m_ij, state_elem)| cur + state_elem * *m_ij)
+            })
+            .collect();
         state.clone_from_slice(&new_state[..state.len()]);
         Ok(())
     }
 
+    /// Apply a [`SparseMdsMatrix`] in place of a dense MDS product. This costs `2t - 1`
+    /// multiplications instead of `t^2`: one dense dot product for the new last element, plus
+    /// one multiplication per remaining element to mix in the last element of `state`.
+    #[tracing::instrument(target = "r1cs", skip(self, sparse))]
+    fn apply_sparse_mds(
+        &self,
+        state: &mut [FpVar<F>],
+        sparse: &SparseMdsMatrix<F>,
+    ) -> Result<(), SynthesisError> {
+        let t = state.len();
+        let last_elem = state[t - 1].clone();
+        let mut last = &last_elem * sparse.row[t - 1];
+        for (row_elem, state_elem) in sparse.row[..t - 1].iter().zip(&state[..t - 1]) {
+            last += state_elem * *row_elem;
+        }
+
+        let (head, tail) = state.split_at_mut(t - 1);
+        for (v_hat, state_elem) in sparse.col_hat.iter().zip(head.iter_mut()) {
+            *state_elem += &last_elem * *v_hat;
+        }
+        tail[0] = last;
+
+        Ok(())
+    }
+
     #[tracing::instrument(target = "r1cs", skip(self))]
     fn permute(&mut self) -> Result<(), SynthesisError> {
         let full_rounds_over_2 = self.full_rounds / 2;
+        let partial_rounds = self.partial_rounds;
         let mut state = self.state.clone();
+
         for i in 0..full_rounds_over_2 {
             self.apply_ark(&mut state, i as usize)?;
             self.apply_s_box(&mut state, true)?;
-            self.apply_mds(&mut state)?;
+            match &self.optimized_mds {
+                // the last full round's MDS application doubles as the boundary into the
+                // partial rounds, so it gets replaced by the first sparse factor.
+                Some(optimized) if i + 1 == full_rounds_over_2 && partial_rounds > 0 => {
+                    self.apply_sparse_mds(&mut state, &optimized.sparse[0])?;
+                }
+                _ => self.apply_mds(&mut state)?,
+            }
         }
-        for i in full_rounds_over_2..(full_rounds_over_2 + self.partial_rounds) {
-            self.apply_ark(&mut state, i as usize)?;
+
+        for r in 0..partial_rounds {
+            self.apply_ark(&mut state, (full_rounds_over_2 + r) as usize)?;
             self.apply_s_box(&mut state, false)?;
-            self.apply_mds(&mut state)?;
+            match &self.optimized_mds {
+                Some(optimized) if r + 1 < partial_rounds => {
+                    self.apply_sparse_mds(&mut state, &optimized.sparse[(r + 1) as usize])?;
+                }
+                Some(optimized) => self.apply_dense_mds(&mut state, &optimized.m_final)?,
+                None => self.apply_mds(&mut state)?,
+            }
         }
 
-        for i in
-            (full_rounds_over_2 + self.partial_rounds)..(self.partial_rounds + self.full_rounds)
-        {
+        for i in (full_rounds_over_2 + partial_rounds)..(partial_rounds + self.full_rounds) {
             self.apply_ark(&mut state, i as usize)?;
             self.apply_s_box(&mut state, true)?;
             self.apply_mds(&mut state)?;
@@ -184,6 +297,131 @@ impl<F: PrimeField> PoseidonSpongeVar<F> {
             rate_start_index = 0;
         }
     }
+
+    /// Advance `io_pattern_index`/`io_pattern_remaining` past any zero-length entries, which are
+    /// vacuously satisfied and would otherwise never be matched by a real (non-zero-length) call.
+    fn skip_empty_io_pattern_ops(&mut self) {
+        let pattern = match &self.io_pattern {
+            Some(pattern) => pattern,
+            None => return,
+        };
+        while pattern
+            .get(self.io_pattern_index)
+            .map(|op| op.len() == 0)
+            .unwrap_or(false)
+        {
+            self.io_pattern_index += 1;
+        }
+        self.io_pattern_remaining = pattern
+            .get(self.io_pattern_index)
+            .map(|op| op.len())
+            .unwrap_or(0);
+    }
+
+    /// Check a call of the given `direction` and `len` against the next expected
+    /// `io_pattern` entry, advancing past it on a match. A no-op if no IO pattern was declared.
+    fn check_io_pattern(
+        &mut self,
+        direction: IOPatternDirection,
+        len: usize,
+    ) -> Result<(), SynthesisError> {
+        if self.io_pattern.is_none() {
+            return Ok(());
+        }
+        if len == 0 {
+            return Ok(());
+        }
+
+        let pattern = self.io_pattern.as_ref().unwrap();
+        let matches = pattern
+            .get(self.io_pattern_index)
+            .map(|op| op.direction() == direction && len == self.io_pattern_remaining)
+            .unwrap_or(false);
+        debug_assert!(
+            matches,
+            "sponge call does not match the declared IO pattern at index {}",
+            self.io_pattern_index
+        );
+        if !matches {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        self.io_pattern_index += 1;
+        self.skip_empty_io_pattern_ops();
+        Ok(())
+    }
+
+    /// Like [`CryptographicSpongeVar::new`], but additionally declares the ordered sequence of
+    /// absorb/squeeze operations this sponge is expected to perform.
+    ///
+    /// The pattern is encoded into a single field element and mixed into the initial capacity
+    /// before the first permutation, giving cryptographic domain separation from any other
+    /// transcript built over the same parameters. Every subsequent `absorb` /
+    /// `squeeze_field_elements` call is checked against the next expected entry, so a
+    /// protocol can't accidentally read or write more (or fewer) elements than its transcript
+    /// shape declares.
+    pub fn new_with_io_pattern(
+        cs: ConstraintSystemRef<F>,
+        params: &PoseidonParameters<F>,
+        pattern: Vec<IOPatternOp>,
+    ) -> Self {
+        let mut sponge = <Self as CryptographicSpongeVar<F, PoseidonSponge<F>>>::new(cs, params);
+
+        let tag = io_pattern_tag::<F>(&pattern);
+        for capacity_elem in sponge.state[sponge.rate..].iter_mut() {
+            *capacity_elem += FpVar::constant(tag);
+        }
+
+        sponge.io_pattern = Some(pattern);
+        sponge.io_pattern_index = 0;
+        sponge.io_pattern_tag = Some(tag);
+        sponge.skip_empty_io_pattern_ops();
+
+        sponge
+    }
+
+    /// Apply the Poseidon permutation directly to the sponge's current state, without any
+    /// absorb/squeeze bookkeeping. Exposed as a standalone primitive (e.g. a block cipher over
+    /// the state) for gadgets built on top of the round logic, such as a compression function or
+    /// a Merkle-tree hash.
+    pub fn permutation(&mut self) -> Result<(), SynthesisError> {
+        self.permute()
+    }
+
+    /// Apply the full/partial/full Poseidon round schedule to an explicit state and return the
+    /// result, on a temporary sponge built from `params` rather than on an existing one. `state`
+    /// must have `rate + capacity` elements, the same width `params` was built for.
+    pub fn permute_state(
+        cs: ConstraintSystemRef<F>,
+        params: &PoseidonParameters<F>,
+        state: Vec<FpVar<F>>,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let mut sponge = <Self as CryptographicSpongeVar<F, PoseidonSponge<F>>>::new(cs, params);
+        sponge.state = state;
+        sponge.permutation()?;
+        Ok(sponge.state)
+    }
+
+    /// Restore the sponge to the state it had immediately after construction — the zeroed state
+    /// (plus the IO-pattern tag, if one was declared), fresh `Absorbing` mode, and the IO pattern
+    /// rewound to its first entry — without rebuilding it from `PoseidonParameters`. Recursive
+    /// protocols that repeatedly re-initialize the same sponge instance across folding steps can
+    /// use this instead of constructing a new one each time.
+    pub fn reset(&mut self) {
+        let zero = FpVar::<F>::zero();
+        self.state = vec![zero; self.rate + self.capacity];
+        if let Some(tag) = self.io_pattern_tag {
+            for capacity_elem in self.state[self.rate..].iter_mut() {
+                *capacity_elem += FpVar::constant(tag);
+            }
+        }
+        self.mode = PoseidonSpongeMode::Absorbing {
+            next_absorb_index: 0,
+        };
+
+        self.io_pattern_index = 0;
+        self.skip_empty_io_pattern_ops();
+    }
 }
 
 impl<F: PrimeField> CryptographicSpongeVar<F, PoseidonSponge<F>> for PoseidonSpongeVar<F> {
@@ -199,8 +437,10 @@ impl<F: PrimeField> CryptographicSpongeVar<F, PoseidonSponge<F>> for PoseidonSpo
 
         let ark = params.ark.to_vec();
 
-        let rate = 2;
-        let capacity = 1;
+        let optimized_mds = params.optimized_mds.clone();
+
+        let rate = params.rate;
+        let capacity = params.capacity;
         let zero = FpVar::<F>::zero();
         let state = vec![zero; rate + capacity];
         let mode = PoseidonSpongeMode::Absorbing {
@@ -214,11 +454,17 @@ impl<F: PrimeField> CryptographicSpongeVar<F, PoseidonSponge<F>> for PoseidonSpo
             alpha,
             ark,
             mds,
+            optimized_mds,
 
             state,
             rate,
             capacity,
             mode,
+
+            io_pattern: None,
+            io_pattern_index: 0,
+            io_pattern_remaining: 0,
+            io_pattern_tag: None,
         }
     }
 
@@ -233,6 +479,7 @@ impl<F: PrimeField> CryptographicSpongeVar<F, PoseidonSponge<F>> for PoseidonSpo
         if input.is_empty() {
             return Ok(());
         }
+        self.check_io_pattern(IOPatternDirection::Absorb, input.len())?;
 
         match self.mode {
             PoseidonSpongeMode::Absorbing { next_absorb_index } => {
@@ -258,7 +505,7 @@ impl<F: PrimeField> CryptographicSpongeVar<F, PoseidonSponge<F>> for PoseidonSpo
     fn squeeze_bytes(&mut self, num_bytes: usize) -> Result<Vec<UInt8<F>>, SynthesisError> {
         let usable_bytes = (F::Params::CAPACITY / 8) as usize;
 
-        let num_elements = (num_bytes + usable_bytes - 1) / usable_bytes;
+        let num_elements = num_bytes.div_ceil(usable_bytes);
         let src_elements = self.squeeze_field_elements(num_elements)?;
 
         let mut bytes: Vec<UInt8<F>> = Vec::with_capacity(usable_bytes * num_elements);
@@ -274,7 +521,7 @@ impl<F: PrimeField> CryptographicSpongeVar<F, PoseidonSponge<F>> for PoseidonSpo
     fn squeeze_bits(&mut self, num_bits: usize) -> Result<Vec<Boolean<F>>, SynthesisError> {
         let usable_bits = F::Params::CAPACITY as usize;
 
-        let num_elements = (num_bits + usable_bits - 1) / usable_bits;
+        let num_elements = num_bits.div_ceil(usable_bits);
         let src_elements = self.squeeze_field_elements(num_elements)?;
 
         let mut bits: Vec<Boolean<F>> = Vec::with_capacity(usable_bits * num_elements);
@@ -291,6 +538,7 @@ impl<F: PrimeField> CryptographicSpongeVar<F, PoseidonSponge<F>> for PoseidonSpo
         &mut self,
         num_elements: usize,
     ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        self.check_io_pattern(IOPatternDirection::Squeeze, num_elements)?;
         let zero = FpVar::zero();
         let mut squeezed_elems = vec![zero; num_elements];
         match self.mode {
@@ -317,7 +565,7 @@ impl<F: PrimeField> CryptographicSpongeVar<F, PoseidonSponge<F>> for PoseidonSpo
 #[cfg(test)]
 mod tests {
     use crate::constraints::CryptographicSpongeVar;
-    use crate::poseidon::constraints::PoseidonSpongeVar;
+    use crate::poseidon::constraints::{io_pattern_tag, IOPatternOp, PoseidonSpongeVar};
     use crate::poseidon::tests::poseidon_parameters_for_test;
     use crate::poseidon::PoseidonSponge;
     use crate::{CryptographicSponge, FieldBasedCryptographicSponge};
@@ -369,4 +617,228 @@ mod tests {
         assert_eq!(squeeze2.value().unwrap(), squeeze1);
         assert!(cs.is_satisfied().unwrap());
     }
+
+    #[test]
+    fn optimized_mds_matches_dense_mds() {
+        let mut rng = test_rng();
+        let cs = ConstraintSystem::new_ref();
+
+        let dense_params = poseidon_parameters_for_test();
+        let optimized_params = poseidon_parameters_for_test().with_optimized_mds();
+
+        let absorb: Vec<_> = (0..5).map(|_| Fr::rand(&mut rng)).collect();
+        let absorb_var: Vec<_> = absorb
+            .iter()
+            .map(|v| FpVar::new_input(ns!(cs, "absorb"), || Ok(*v)).unwrap())
+            .collect();
+
+        let mut dense_sponge = PoseidonSpongeVar::<Fr>::new(cs.clone(), &dense_params);
+        let mut optimized_sponge = PoseidonSpongeVar::<Fr>::new(cs.clone(), &optimized_params);
+
+        dense_sponge.absorb(&absorb_var).unwrap();
+        optimized_sponge.absorb(&absorb_var).unwrap();
+
+        let dense_squeeze = dense_sponge.squeeze_field_elements(3).unwrap();
+        let optimized_squeeze = optimized_sponge.squeeze_field_elements(3).unwrap();
+
+        assert_eq!(
+            dense_squeeze.value().unwrap(),
+            optimized_squeeze.value().unwrap()
+        );
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn optimized_mds_matches_dense_mds_with_zero_partial_rounds() {
+        let mut rng = test_rng();
+        let cs = ConstraintSystem::new_ref();
+
+        // partial_rounds == 0 leaves the sparse decomposition empty; permute must fall back to
+        // the dense mds at the full-round boundary instead of indexing into it.
+        let full_rounds = 8;
+        let mds = vec![
+            vec![Fr::from(1u64), Fr::from(0u64), Fr::from(1u64)],
+            vec![Fr::from(1u64), Fr::from(1u64), Fr::from(0u64)],
+            vec![Fr::from(0u64), Fr::from(1u64), Fr::from(1u64)],
+        ];
+        let ark: Vec<_> = (0..full_rounds)
+            .map(|_| vec![Fr::from(1u64), Fr::from(1u64), Fr::from(1u64)])
+            .collect();
+        let dense_params =
+            crate::poseidon::PoseidonParameters::new(full_rounds, 0, 17, mds.clone(), ark.clone(), 2, 1);
+        let optimized_params =
+            crate::poseidon::PoseidonParameters::new(full_rounds, 0, 17, mds, ark, 2, 1)
+                .with_optimized_mds();
+
+        let absorb: Vec<_> = (0..2).map(|_| Fr::rand(&mut rng)).collect();
+        let absorb_var: Vec<_> = absorb
+            .iter()
+            .map(|v| FpVar::new_input(ns!(cs, "absorb"), || Ok(*v)).unwrap())
+            .collect();
+
+        let mut dense_sponge = PoseidonSpongeVar::<Fr>::new(cs.clone(), &dense_params);
+        let mut optimized_sponge = PoseidonSpongeVar::<Fr>::new(cs.clone(), &optimized_params);
+
+        dense_sponge.absorb(&absorb_var).unwrap();
+        optimized_sponge.absorb(&absorb_var).unwrap();
+
+        let dense_squeeze = dense_sponge.squeeze_field_elements(3).unwrap();
+        let optimized_squeeze = optimized_sponge.squeeze_field_elements(3).unwrap();
+
+        assert_eq!(
+            dense_squeeze.value().unwrap(),
+            optimized_squeeze.value().unwrap()
+        );
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn with_optimized_mds_panics_on_zero_full_rounds() {
+        // There is no round left to fold the partial phase's leftover constants into when
+        // full_rounds == 0, so with_optimized_mds must refuse this rather than silently
+        // producing a decomposition permute can't apply correctly.
+        let partial_rounds = 5;
+        let mds = vec![
+            vec![Fr::from(1u64), Fr::from(0u64), Fr::from(1u64)],
+            vec![Fr::from(1u64), Fr::from(1u64), Fr::from(0u64)],
+            vec![Fr::from(0u64), Fr::from(1u64), Fr::from(1u64)],
+        ];
+        let ark = (0..partial_rounds)
+            .map(|_| vec![Fr::from(1u64), Fr::from(1u64), Fr::from(1u64)])
+            .collect();
+
+        let _ = crate::poseidon::PoseidonParameters::new(0, partial_rounds, 17, mds, ark, 2, 1)
+            .with_optimized_mds();
+    }
+
+    #[test]
+    fn io_pattern_accepts_the_declared_sequence() {
+        let mut rng = test_rng();
+        let cs = ConstraintSystem::new_ref();
+        let sponge_params = poseidon_parameters_for_test();
+
+        let pattern = vec![
+            IOPatternOp::Absorb(3),
+            IOPatternOp::Squeeze(1),
+            IOPatternOp::Absorb(2),
+            IOPatternOp::Squeeze(1),
+        ];
+
+        let absorb3: Vec<_> = (0..3)
+            .map(|_| FpVar::new_input(ns!(cs, "absorb3"), || Ok(Fr::rand(&mut rng))).unwrap())
+            .collect();
+        let absorb2: Vec<_> = (0..2)
+            .map(|_| FpVar::new_input(ns!(cs, "absorb2"), || Ok(Fr::rand(&mut rng))).unwrap())
+            .collect();
+
+        let mut sponge =
+            PoseidonSpongeVar::<Fr>::new_with_io_pattern(cs.clone(), &sponge_params, pattern);
+
+        sponge.absorb(&absorb3).unwrap();
+        sponge.squeeze_field_elements(1).unwrap();
+        sponge.absorb(&absorb2).unwrap();
+        sponge.squeeze_field_elements(1).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn io_pattern_tag_distinguishes_length_from_direction() {
+        // A naive fixed-width encoding of (direction, length) can make a long op in one
+        // direction collide with a short op in the other; these two single-op patterns must
+        // not produce the same tag.
+        let absorb_pattern = vec![IOPatternOp::Absorb(70000)];
+        let squeeze_pattern = vec![IOPatternOp::Squeeze(4464)];
+
+        assert_ne!(
+            io_pattern_tag::<Fr>(&absorb_pattern),
+            io_pattern_tag::<Fr>(&squeeze_pattern)
+        );
+    }
+
+    #[test]
+    fn io_pattern_skips_a_zero_length_entry() {
+        let mut rng = test_rng();
+        let cs = ConstraintSystem::new_ref();
+        let sponge_params = poseidon_parameters_for_test();
+
+        // A zero-length entry has nothing to check a call against, so it must not block the
+        // real `Absorb(3)` that follows it from being matched.
+        let pattern = vec![IOPatternOp::Absorb(0), IOPatternOp::Absorb(3)];
+        let absorb3: Vec<_> = (0..3)
+            .map(|_| FpVar::new_input(ns!(cs, "absorb3"), || Ok(Fr::rand(&mut rng))).unwrap())
+            .collect();
+
+        let mut sponge =
+            PoseidonSpongeVar::<Fr>::new_with_io_pattern(cs.clone(), &sponge_params, pattern);
+
+        sponge.absorb(&absorb3).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn io_pattern_rejects_an_out_of_order_call() {
+        let mut rng = test_rng();
+        let cs = ConstraintSystem::new_ref();
+        let sponge_params = poseidon_parameters_for_test();
+
+        let pattern = vec![IOPatternOp::Absorb(3), IOPatternOp::Squeeze(1)];
+        let absorb2: Vec<_> = (0..2)
+            .map(|_| FpVar::new_input(ns!(cs, "absorb2"), || Ok(Fr::rand(&mut rng))).unwrap())
+            .collect();
+
+        let mut sponge =
+            PoseidonSpongeVar::<Fr>::new_with_io_pattern(cs.clone(), &sponge_params, pattern);
+
+        // The pattern declares `Absorb(3)` first; absorbing 2 elements mismatches it.
+        let _ = sponge.absorb(&absorb2);
+    }
+
+    #[test]
+    fn reset_matches_a_fresh_sponge() {
+        let mut rng = test_rng();
+        let cs = ConstraintSystem::new_ref();
+        let sponge_params = poseidon_parameters_for_test();
+
+        let absorb: Vec<_> = (0..4)
+            .map(|_| FpVar::new_input(ns!(cs, "absorb"), || Ok(Fr::rand(&mut rng))).unwrap())
+            .collect();
+
+        let mut sponge = PoseidonSpongeVar::<Fr>::new(cs.clone(), &sponge_params);
+        sponge.absorb(&absorb).unwrap();
+        sponge.squeeze_field_elements(1).unwrap();
+        sponge.reset();
+
+        let mut fresh_sponge = PoseidonSpongeVar::<Fr>::new(cs.clone(), &sponge_params);
+        assert_eq!(
+            sponge.state.value().unwrap(),
+            fresh_sponge.state.value().unwrap()
+        );
+
+        sponge.absorb(&absorb).unwrap();
+        fresh_sponge.absorb(&absorb).unwrap();
+        let squeeze1 = sponge.squeeze_field_elements(2).unwrap();
+        let squeeze2 = fresh_sponge.squeeze_field_elements(2).unwrap();
+        assert_eq!(squeeze1.value().unwrap(), squeeze2.value().unwrap());
+    }
+
+    #[test]
+    fn permute_state_matches_permutation() {
+        let mut rng = test_rng();
+        let cs = ConstraintSystem::new_ref();
+        let sponge_params = poseidon_parameters_for_test();
+
+        let input: Vec<_> = (0..3)
+            .map(|_| FpVar::new_input(ns!(cs, "state"), || Ok(Fr::rand(&mut rng))).unwrap())
+            .collect();
+
+        let mut sponge = PoseidonSpongeVar::<Fr>::new(cs.clone(), &sponge_params);
+        sponge.state = input.clone();
+        sponge.permutation().unwrap();
+
+        let permuted = PoseidonSpongeVar::permute_state(cs.clone(), &sponge_params, input).unwrap();
+
+        assert_eq!(sponge.state.value().unwrap(), permuted.value().unwrap());
+    }
 }
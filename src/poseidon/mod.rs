@@ -0,0 +1,540 @@
+use crate::{Absorb, CryptographicSponge, FieldBasedCryptographicSponge};
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use ark_std::vec::Vec;
+
+/// The R1CS gadget counterpart of `PoseidonSponge`.
+pub mod constraints;
+
+/// Parameters used by the Poseidon sponge
+#[derive(Clone, Debug)]
+pub struct PoseidonParameters<F: PrimeField> {
+    /// number of rounds in a full-round operation
+    pub full_rounds: u32,
+    /// number of rounds in a partial-round operation
+    pub partial_rounds: u32,
+    /// Exponent used in S-boxes
+    pub alpha: u64,
+    /// Additive Round keys. These are added before each MDS matrix application to make it an affine shift.
+    /// They are indexed by `ark[round_num][state_element_index]`
+    pub ark: Vec<Vec<F>>,
+    /// Maximally Distance Separating Matrix.
+    pub mds: Vec<Vec<F>>,
+    /// the rate
+    pub rate: usize,
+    /// the capacity
+    pub capacity: usize,
+    /// Precomputed sparse-matrix decomposition of `mds`, used to cut the cost of the partial
+    /// rounds' linear layer. `None` falls back to the dense `mds` on every round.
+    pub optimized_mds: Option<OptimizedMds<F>>,
+}
+
+impl<F: PrimeField> PoseidonParameters<F> {
+    /// Initialize the parameter for Poseidon Sponge.
+    pub fn new(
+        full_rounds: u32,
+        partial_rounds: u32,
+        alpha: u64,
+        mds: Vec<Vec<F>>,
+        ark: Vec<Vec<F>>,
+        rate: usize,
+        capacity: usize,
+    ) -> Self {
+        Self {
+            full_rounds,
+            partial_rounds,
+            alpha,
+            mds,
+            ark,
+            rate,
+            capacity,
+            optimized_mds: None,
+        }
+    }
+
+    /// Precompute the sparse-matrix decomposition of `self.mds` (see [`OptimizedMds`]) and fold
+    /// the partial-round constants through it, so that `permute` can replace the dense `t`-by-`t`
+    /// MDS product of the last full round before the partial phase and of every partial round
+    /// but the last with a `2t - 1`-multiplication sparse one. The last partial round applies
+    /// the dense matrix `m_final` instead.
+    ///
+    /// The decomposition is mathematically equivalent to the dense path: it produces
+    /// bit-identical output, just with fewer multiplications (and, in-circuit, fewer R1CS
+    /// constraints) per partial round.
+    ///
+    /// `partial_rounds == 0` is supported (the decomposition is simply empty and `permute` falls
+    /// back to the dense `mds`). Panics if `full_rounds < 2`, since the partial phase then lacks
+    /// a full round before it to apply the first sparse factor in, or a full round after it to
+    /// fold the leftover constants into.
+    pub fn with_optimized_mds(mut self) -> Self {
+        assert!(
+            self.full_rounds >= 2,
+            "with_optimized_mds requires full_rounds >= 2: the first sparse factor is applied in \
+             the last full round before the partial-round phase, and the leftover constants from \
+             that phase are folded into the ark of the full round that follows it"
+        );
+        let t = self.mds.len();
+        let full_rounds_over_2 = (self.full_rounds / 2) as usize;
+        let partial_rounds = self.partial_rounds as usize;
+
+        // Iterate the M = M' * M'' decomposition `partial_rounds` times, peeling one sparse
+        // factor off the accumulated matrix at each step. `sparse[0]` is a factor of `mds`
+        // itself, so it is what gets applied at the boundary into the partial rounds; `matrix`
+        // ends up holding the dense matrix that reconciles everything at the last partial round.
+        let mut matrix = self.mds.clone();
+        let mut sparse = Vec::with_capacity(partial_rounds);
+        for _ in 0..partial_rounds {
+            let (m_prime, sparse_factor) = factor_to_sparse(&matrix);
+            sparse.push(sparse_factor);
+            matrix = mat_mat_mul(&self.mds, &m_prime);
+        }
+        let m_final = matrix;
+
+        // Fold the non-s-boxed-lane constants of each partial round forward through the dense
+        // linear layer that follows them, leaving only the s-boxed (last) lane's constant to be
+        // applied per round. The leftover carried past the final partial round is a plain vector
+        // that gets absorbed into the ark of the first full round following the partial phase.
+        let mut pending = vec![F::zero(); t];
+        for r in 0..partial_rounds {
+            let idx = full_rounds_over_2 + r;
+            let mut folded = self.ark[idx].clone();
+            for (elem, pending_elem) in folded.iter_mut().zip(&pending) {
+                *elem += pending_elem;
+            }
+
+            let mut rest = folded.clone();
+            rest[t - 1] = F::zero();
+
+            self.ark[idx] = vec![F::zero(); t];
+            self.ark[idx][t - 1] = folded[t - 1];
+
+            pending = mat_vec_mul(&self.mds, &rest);
+        }
+        let after_partial = full_rounds_over_2 + partial_rounds;
+        for (elem, pending_elem) in self.ark[after_partial].iter_mut().zip(&pending) {
+            *elem += pending_elem;
+        }
+
+        self.optimized_mds = Some(OptimizedMds { sparse, m_final });
+        self
+    }
+}
+
+/// A sparse replacement for one partial round's dense MDS application. It represents the matrix
+/// `[[I_{t-1}, v_hat], [w^T, m_tt]]`: dense in its last row and the head of its last column,
+/// identity everywhere else, matching the lane the s-box is applied to. Applying it costs
+/// `2t - 1` multiplications instead of `t^2`.
+#[derive(Clone, Debug)]
+pub struct SparseMdsMatrix<F: PrimeField> {
+    /// the dense last row, `t` entries: `[w_1, ..., w_{t-1}, m_tt]`
+    pub row: Vec<F>,
+    /// the dense head of the last column, `t - 1` entries: `[v_hat_1, ..., v_hat_{t-1}]`
+    pub col_hat: Vec<F>,
+}
+
+/// The precomputed sparse-matrix form of an MDS matrix, used to speed up Poseidon's partial
+/// rounds. See [`PoseidonParameters::with_optimized_mds`] for how it is derived.
+#[derive(Clone, Debug)]
+pub struct OptimizedMds<F: PrimeField> {
+    /// one sparse matrix per partial round; `sparse[0]` is applied at the boundary into the
+    /// partial rounds, and `sparse[r + 1]` at the `r`-th partial round for every round but the
+    /// last
+    pub sparse: Vec<SparseMdsMatrix<F>>,
+    /// the dense matrix applied at the last partial round, reconciling the lag the sparse
+    /// factors accumulate in the non-s-boxed lanes
+    pub m_final: Vec<Vec<F>>,
+}
+
+/// Apply a [`SparseMdsMatrix`] to a state vector.
+fn apply_sparse_mds_vec<F: PrimeField>(sparse: &SparseMdsMatrix<F>, state: &[F]) -> Vec<F> {
+    let t = state.len();
+    let mut new_state = Vec::with_capacity(t);
+
+    for (v_hat, state_elem) in sparse.col_hat.iter().zip(&state[..t - 1]) {
+        new_state.push(*state_elem + *v_hat * state[t - 1]);
+    }
+
+    let mut last = sparse.row[t - 1] * state[t - 1];
+    for (row_elem, state_elem) in sparse.row[..t - 1].iter().zip(&state[..t - 1]) {
+        last += *row_elem * state_elem;
+    }
+    new_state.push(last);
+
+    new_state
+}
+
+/// Factor a dense matrix `m = [[m_hat, v], [w^T, m_tt]]` into `m' * m''`, where `m'` has `m_hat`
+/// in its upper-left block and the identity elsewhere, and `m''` is the [`SparseMdsMatrix`] with
+/// `v_hat = m_hat^{-1} * v`. Returns `(m', m'')`.
+fn factor_to_sparse<F: PrimeField>(m: &[Vec<F>]) -> (Vec<Vec<F>>, SparseMdsMatrix<F>) {
+    let t = m.len();
+    let m_tt = m[t - 1][t - 1];
+    let w = m[t - 1][..t - 1].to_vec();
+    let v: Vec<F> = (0..t - 1).map(|i| m[i][t - 1]).collect();
+    let m_hat: Vec<Vec<F>> = (0..t - 1).map(|i| m[i][..t - 1].to_vec()).collect();
+
+    let m_hat_inv = invert_matrix(&m_hat);
+    let v_hat = mat_vec_mul(&m_hat_inv, &v);
+
+    let mut m_prime = vec![vec![F::zero(); t]; t];
+    for i in 0..(t - 1) {
+        m_prime[i][..t - 1].clone_from_slice(&m_hat[i]);
+    }
+    m_prime[t - 1][t - 1] = F::one();
+
+    let mut row = w;
+    row.push(m_tt);
+
+    (m_prime, SparseMdsMatrix { row, col_hat: v_hat })
+}
+
+fn mat_vec_mul<F: PrimeField>(m: &[Vec<F>], v: &[F]) -> Vec<F> {
+    m.iter()
+        .map(|row| {
+            row.iter()
+                .zip(v)
+                .fold(F::zero(), |acc, (m_ij, v_j)| acc + *m_ij * v_j)
+        })
+        .collect()
+}
+
+fn mat_mat_mul<F: PrimeField>(a: &[Vec<F>], b: &[Vec<F>]) -> Vec<Vec<F>> {
+    let n = a.len();
+    let m = b[0].len();
+    let mut out = vec![vec![F::zero(); m]; n];
+    for i in 0..n {
+        for k in 0..a[i].len() {
+            if a[i][k].is_zero() {
+                continue;
+            }
+            for j in 0..m {
+                out[i][j] += a[i][k] * b[k][j];
+            }
+        }
+    }
+    out
+}
+
+/// Invert a square matrix over `F` via Gauss-Jordan elimination. The MDS property guarantees
+/// that every square sub-block of an MDS matrix is invertible, so this never encounters a
+/// singular matrix for the inputs `with_optimized_mds` passes in.
+fn invert_matrix<F: PrimeField>(m: &[Vec<F>]) -> Vec<Vec<F>> {
+    let n = m.len();
+    let mut aug: Vec<Vec<F>> = m
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut row = row.clone();
+            row.extend((0..n).map(|j| if i == j { F::one() } else { F::zero() }));
+            row
+        })
+        .collect();
+
+    for col in 0..n {
+        let pivot_row = (col..n)
+            .find(|&r| !aug[r][col].is_zero())
+            .expect("matrix is singular");
+        aug.swap(col, pivot_row);
+
+        let inv_pivot = aug[col][col].inverse().expect("pivot is nonzero");
+        for entry in aug[col].iter_mut() {
+            *entry *= inv_pivot;
+        }
+
+        for row in 0..n {
+            if row == col {
+                continue;
+            }
+            let factor = aug[row][col];
+            if factor.is_zero() {
+                continue;
+            }
+            let pivot_row = aug[col].clone();
+            for (entry, pivot_entry) in aug[row].iter_mut().zip(&pivot_row) {
+                *entry -= *pivot_entry * factor;
+            }
+        }
+    }
+
+    aug.into_iter().map(|row| row[n..].to_vec()).collect()
+}
+
+#[derive(Clone)]
+/// The mode in which a `PoseidonSponge` is operating.
+pub enum PoseidonSpongeMode {
+    /// The sponge is currently absorbing input elements. The next call of `absorb` will start
+    /// absorbing at `next_absorb_index`; the next `squeeze` permutes first and starts at 0.
+    Absorbing {
+        /// next position of the state to be added to when absorbing.
+        next_absorb_index: usize,
+    },
+    /// The sponge is currently squeezing output elements. The next call of
+    /// `squeeze` will start squeezing at `next_squeeze_index`.
+    Squeezing {
+        /// next position of the state to be outputted when squeezing.
+        next_squeeze_index: usize,
+    },
+}
+
+#[derive(Clone)]
+/// A duplex sponge based using the Poseidon permutation.
+///
+/// This implementation of Poseidon is entirely from Fractal's implementation in [COS20][cos]
+/// with small syntax changes.
+///
+/// [cos]: https://eprint.iacr.org/2019/1076
+pub struct PoseidonSponge<F: PrimeField> {
+    /// Sponge Parameters
+    pub parameters: PoseidonParameters<F>,
+
+    // Sponge State
+    /// current sponge's state (current elements in the permutation block)
+    pub state: Vec<F>,
+    /// current mode (whether its absorbing or squeezing)
+    pub mode: PoseidonSpongeMode,
+}
+
+impl<F: PrimeField> PoseidonSponge<F> {
+    fn apply_ark(&mut self, round_number: usize) {
+        for (i, state_elem) in self.state.iter_mut().enumerate() {
+            *state_elem += &self.parameters.ark[round_number][i];
+        }
+    }
+
+    fn apply_s_box(&mut self, is_full_round: bool) {
+        if is_full_round {
+            for elem in self.state.iter_mut() {
+                *elem = elem.pow([self.parameters.alpha]);
+            }
+        } else {
+            let last = self.state.len() - 1;
+            self.state[last] = self.state[last].pow([self.parameters.alpha]);
+        }
+    }
+
+    fn apply_mds(&mut self) {
+        self.state = mat_vec_mul(&self.parameters.mds, &self.state);
+    }
+
+    fn permute(&mut self) {
+        let full_rounds_over_2 = (self.parameters.full_rounds / 2) as usize;
+        let partial_rounds = self.parameters.partial_rounds as usize;
+
+        for i in 0..full_rounds_over_2 {
+            self.apply_ark(i);
+            self.apply_s_box(true);
+            // Borrow `optimized_mds` fresh (rather than cloning it once up front) — it's a
+            // disjoint field from `self.state`, so the borrow checker is happy with reading one
+            // while writing the other, and every round skips re-allocating the whole thing.
+            match &self.parameters.optimized_mds {
+                // the last full round's MDS application doubles as the boundary into the
+                // partial rounds, so it gets replaced by the first sparse factor.
+                Some(optimized) if i + 1 == full_rounds_over_2 && partial_rounds > 0 => {
+                    self.state = apply_sparse_mds_vec(&optimized.sparse[0], &self.state);
+                }
+                _ => self.apply_mds(),
+            }
+        }
+
+        for r in 0..partial_rounds {
+            self.apply_ark(full_rounds_over_2 + r);
+            self.apply_s_box(false);
+            match &self.parameters.optimized_mds {
+                Some(optimized) if r + 1 < partial_rounds => {
+                    self.state = apply_sparse_mds_vec(&optimized.sparse[r + 1], &self.state);
+                }
+                Some(optimized) => self.state = mat_vec_mul(&optimized.m_final, &self.state),
+                None => self.apply_mds(),
+            }
+        }
+
+        for i in (full_rounds_over_2 + partial_rounds)
+            ..(partial_rounds + self.parameters.full_rounds as usize)
+        {
+            self.apply_ark(i);
+            self.apply_s_box(true);
+            self.apply_mds();
+        }
+    }
+
+    fn absorb_internal(&mut self, mut rate_start_index: usize, elements: &[F]) {
+        let mut remaining_elements = elements;
+        loop {
+            if rate_start_index + remaining_elements.len() <= self.parameters.rate {
+                for (i, element) in remaining_elements.iter().enumerate() {
+                    self.state[i + rate_start_index] += element;
+                }
+                self.mode = PoseidonSpongeMode::Absorbing {
+                    next_absorb_index: rate_start_index + remaining_elements.len(),
+                };
+                return;
+            }
+            let num_elements_absorbed = self.parameters.rate - rate_start_index;
+            for (i, element) in remaining_elements
+                .iter()
+                .enumerate()
+                .take(num_elements_absorbed)
+            {
+                self.state[i + rate_start_index] += element;
+            }
+            self.permute();
+            remaining_elements = &remaining_elements[num_elements_absorbed..];
+            rate_start_index = 0;
+        }
+    }
+
+    fn squeeze_internal(&mut self, mut rate_start_index: usize, output: &mut [F]) {
+        let mut remaining_output = output;
+        loop {
+            if rate_start_index + remaining_output.len() <= self.parameters.rate {
+                remaining_output.clone_from_slice(
+                    &self.state[rate_start_index..(remaining_output.len() + rate_start_index)],
+                );
+                self.mode = PoseidonSpongeMode::Squeezing {
+                    next_squeeze_index: rate_start_index + remaining_output.len(),
+                };
+                return;
+            }
+            let num_elements_squeezed = self.parameters.rate - rate_start_index;
+            remaining_output[..num_elements_squeezed].clone_from_slice(
+                &self.state[rate_start_index..(num_elements_squeezed + rate_start_index)],
+            );
+
+            if remaining_output.len() != self.parameters.rate {
+                self.permute();
+            }
+            remaining_output = &mut remaining_output[num_elements_squeezed..];
+            rate_start_index = 0;
+        }
+    }
+}
+
+impl<F: PrimeField> CryptographicSponge for PoseidonSponge<F> {
+    type Parameters = PoseidonParameters<F>;
+
+    fn new(parameters: &Self::Parameters) -> Self {
+        let state = vec![F::zero(); parameters.rate + parameters.capacity];
+        let mode = PoseidonSpongeMode::Absorbing {
+            next_absorb_index: 0,
+        };
+
+        Self {
+            parameters: parameters.clone(),
+            state,
+            mode,
+        }
+    }
+
+    fn absorb(&mut self, input: &impl Absorb) {
+        let elements = input.to_sponge_field_elements_as_vec();
+        if elements.is_empty() {
+            return;
+        }
+
+        match self.mode {
+            PoseidonSpongeMode::Absorbing { next_absorb_index } => {
+                let mut absorb_index = next_absorb_index;
+                if absorb_index == self.parameters.rate {
+                    self.permute();
+                    absorb_index = 0;
+                }
+                self.absorb_internal(absorb_index, elements.as_slice());
+            }
+            PoseidonSpongeMode::Squeezing {
+                next_squeeze_index: _,
+            } => {
+                self.permute();
+                self.absorb_internal(0, elements.as_slice());
+            }
+        };
+    }
+
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Vec<u8> {
+        let usable_bytes = (F::Params::CAPACITY / 8) as usize;
+
+        let num_elements = num_bytes.div_ceil(usable_bytes);
+        let src_elements = self.squeeze_native_field_elements(num_elements);
+
+        let mut bytes: Vec<u8> = Vec::with_capacity(usable_bytes * num_elements);
+        for elem in &src_elements {
+            let elem_bytes = elem.into_repr().to_bytes_le();
+            bytes.extend_from_slice(&elem_bytes[..usable_bytes]);
+        }
+
+        bytes.truncate(num_bytes);
+        bytes
+    }
+
+    fn squeeze_bits(&mut self, num_bits: usize) -> Vec<bool> {
+        let usable_bits = F::Params::CAPACITY as usize;
+
+        let num_elements = num_bits.div_ceil(usable_bits);
+        let src_elements = self.squeeze_native_field_elements(num_elements);
+
+        let mut bits = Vec::with_capacity(usable_bits * num_elements);
+        for elem in &src_elements {
+            let elem_bits = elem.into_repr().to_bits_le();
+            bits.extend_from_slice(&elem_bits[..usable_bits]);
+        }
+
+        bits.truncate(num_bits);
+        bits
+    }
+
+    fn squeeze_field_elements<Fr: PrimeField>(&mut self, num_elements: usize) -> Vec<Fr> {
+        // A non-native squeeze goes through a canonical little-endian byte encoding, one
+        // field-sized chunk of bytes per output element.
+        let bytes_per_element = Fr::size_in_bits().div_ceil(8);
+        let bytes = self.squeeze_bytes(bytes_per_element * num_elements);
+        bytes
+            .chunks(bytes_per_element)
+            .map(Fr::from_le_bytes_mod_order)
+            .collect()
+    }
+}
+
+impl<F: PrimeField> FieldBasedCryptographicSponge<F> for PoseidonSponge<F> {
+    fn squeeze_native_field_elements(&mut self, num_elements: usize) -> Vec<F> {
+        let mut squeezed_elems = vec![F::zero(); num_elements];
+        match self.mode {
+            PoseidonSpongeMode::Absorbing {
+                next_absorb_index: _,
+            } => {
+                self.permute();
+                self.squeeze_internal(0, &mut squeezed_elems);
+            }
+            PoseidonSpongeMode::Squeezing { next_squeeze_index } => {
+                let mut squeeze_index = next_squeeze_index;
+                if squeeze_index == self.parameters.rate {
+                    self.permute();
+                    squeeze_index = 0;
+                }
+                self.squeeze_internal(squeeze_index, &mut squeezed_elems);
+            }
+        };
+
+        squeezed_elems
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::poseidon::PoseidonParameters;
+    use ark_ff::PrimeField;
+
+    /// Parameters for a toy Poseidon instance, intended only for the unit tests in this crate.
+    pub(crate) fn poseidon_parameters_for_test<F: PrimeField>() -> PoseidonParameters<F> {
+        let full_rounds = 8;
+        let partial_rounds = 31;
+        let alpha = 17;
+        let mds = vec![
+            vec![F::one(), F::zero(), F::one()],
+            vec![F::one(), F::one(), F::zero()],
+            vec![F::zero(), F::one(), F::one()],
+        ];
+        let ark = (0..(full_rounds + partial_rounds))
+            .map(|_| vec![F::one(), F::one(), F::one()])
+            .collect();
+
+        PoseidonParameters::new(full_rounds, partial_rounds, alpha, mds, ark, 2, 1)
+    }
+}